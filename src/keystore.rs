@@ -1,3 +1,4 @@
+use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use std::iter;
@@ -9,6 +10,10 @@ use dashmap::DashMap;
 ////////////////////////////////////////////////////////////////////////
 #[derive(Debug)]
 struct KeysInner<K: Eq + Hash> {
+    // `ordered[i]` is the key assigned to index `i`; kept alongside
+    // `indices` so iteration can walk keys in insertion order without
+    // depending on `HashMap`'s unspecified iteration order.
+    ordered: Vec<K>,
     indices: HashMap<K, usize>,
     inserts: DashMap<K, SharedKeys<K>>,
 }
@@ -26,26 +31,23 @@ impl<K: Clone + Eq + Hash> KeysInner<K> {
         self.inserts
             .entry(key.clone())
             .or_insert_with(|| {
-                let index = self
-                    .indices
-                    .values()
-                    .copied()
-                    .max()
-                    .map(|index| index + 1)
-                    .unwrap_or(0);
-                SharedKeys::from_iter(
-                    self.indices
-                        .iter()
-                        .map(|(key, &index)| (key.clone(), index))
-                        .chain(iter::once((key, index))),
-                )
+                let index = self.ordered.len();
+                let mut ordered = self.ordered.clone();
+                ordered.push(key.clone());
+                let mut indices = self.indices.clone();
+                indices.insert(key, index);
+                SharedKeys(Arc::new(KeysInner {
+                    ordered,
+                    indices,
+                    inserts: Default::default(),
+                }))
             })
             .value()
             .clone()
     }
 }
 
-impl<K: Eq + Hash> FromIterator<K> for KeysInner<K> {
+impl<K: Clone + Eq + Hash> FromIterator<K> for KeysInner<K> {
     fn from_iter<I: IntoIterator<Item = K>>(keys: I) -> Self {
         Self::from_iter(
             keys.into_iter()
@@ -55,10 +57,30 @@ impl<K: Eq + Hash> FromIterator<K> for KeysInner<K> {
     }
 }
 
-impl<K: Eq + Hash> FromIterator<(K, usize)> for KeysInner<K> {
-    fn from_iter<I: IntoIterator<Item = (K, usize)>>(indices: I) -> Self {
+impl<K: Clone + Eq + Hash> FromIterator<(K, usize)> for KeysInner<K> {
+    fn from_iter<I: IntoIterator<Item = (K, usize)>>(pairs: I) -> Self {
+        // Collecting into `indices` naturally dedups repeated keys,
+        // keeping whichever pair appears last. To size and fill
+        // `ordered` without panicking on repeats, walk *all* pairs
+        // (including any later-superseded ones) rather than just the
+        // survivors left in `indices` once deduped, since a repeated key
+        // can otherwise leave its earlier index without a key to
+        // attribute to it. An index that no pair names at all still
+        // panics below.
+        let pairs: Vec<(K, usize)> = pairs.into_iter().collect();
+        let len = pairs.iter().map(|&(_, index)| index + 1).max().unwrap_or(0);
+        let mut ordered: Vec<Option<K>> = iter::repeat_with(|| None).take(len).collect();
+        for (key, index) in &pairs {
+            ordered[*index] = Some(key.clone());
+        }
+        let ordered = ordered
+            .into_iter()
+            .enumerate()
+            .map(|(index, key)| key.unwrap_or_else(|| panic!("index {index} has no assigned key")))
+            .collect();
         Self {
-            indices: indices.into_iter().collect(),
+            ordered,
+            indices: pairs.into_iter().collect(),
             inserts: Default::default(),
         }
     }
@@ -69,7 +91,11 @@ impl<K: Eq + Hash> FromIterator<(K, usize)> for KeysInner<K> {
 pub struct SharedKeys<K: Eq + Hash>(Arc<KeysInner<K>>);
 
 impl<K: Eq + Hash> SharedKeys<K> {
-    pub fn get_index(&self, key: &K) -> Option<usize> {
+    pub fn get_index<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         self.0.indices.get(key).copied()
     }
 
@@ -77,6 +103,11 @@ impl<K: Eq + Hash> SharedKeys<K> {
         &self.0.indices
     }
 
+    /// Keys in insertion order, indexed the same way as a map's `values`.
+    pub fn ordered_keys(&self) -> &[K] {
+        &self.0.ordered
+    }
+
     fn collect(&self, all_keys: &mut HashSet<Self>) {
         if !all_keys.contains(self) {
             all_keys.insert(self.clone());
@@ -99,13 +130,13 @@ impl<K: Eq + Hash> Clone for SharedKeys<K> {
     }
 }
 
-impl<K: Eq + Hash> FromIterator<K> for SharedKeys<K> {
+impl<K: Clone + Eq + Hash> FromIterator<K> for SharedKeys<K> {
     fn from_iter<I: IntoIterator<Item = K>>(keys: I) -> Self {
         Self(Arc::new(KeysInner::from_iter(keys)))
     }
 }
 
-impl<K: Eq + Hash> FromIterator<(K, usize)> for SharedKeys<K> {
+impl<K: Clone + Eq + Hash> FromIterator<(K, usize)> for SharedKeys<K> {
     fn from_iter<I: IntoIterator<Item = (K, usize)>>(indices: I) -> Self {
         Self(Arc::new(KeysInner::from_iter(indices)))
     }
@@ -183,3 +214,19 @@ impl<K: Clone + Eq + Hash + Send + Sync + 'static> KeyStore<K> for StdKeyStore<K
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_iter_with_repeated_keys_does_not_panic() {
+        // The repeated `1` resolves to the index of its last occurrence,
+        // while `ordered` keeps one entry per input position, rather than
+        // panicking or going out of bounds while sizing `ordered`.
+        let keys = SharedKeys::from_iter([1u8, 2, 1]);
+        assert_eq!(keys.get_index(&1), Some(2));
+        assert_eq!(keys.get_index(&2), Some(1));
+        assert_eq!(keys.ordered_keys().len(), 3);
+    }
+}
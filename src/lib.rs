@@ -1,7 +1,15 @@
 mod keystore;
 mod map;
+mod persistent;
+#[cfg(feature = "rayon")]
+pub mod rayon;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "serde")]
+pub mod serde_seq;
 
 pub use map::NewMap;
+pub use persistent::PersistentMap;
 
 #[cfg(test)]
 mod tests {
@@ -39,6 +47,124 @@ mod tests {
         assert_eq!(map.get(&1), Some(&102));
     }
 
+    #[test]
+    fn from_iter_with_duplicate_keys() {
+        let map = NewMap::<u8, u64>::from_iter([(1, 10), (2, 20), (1, 11)]);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&1), Some(&11));
+        assert_eq!(map.get(&2), Some(&20));
+
+        let mut pairs: Vec<_> = map.iter().collect();
+        pairs.sort();
+        assert_eq!(pairs, vec![(&1, &11), (&2, &20)]);
+    }
+
+    #[test]
+    fn entry_api() {
+        use super::map::Entry;
+
+        let mut map = NewMap::<u8, u64>::default();
+
+        *map.entry(1).or_insert(10) += 1;
+        assert_eq!(map.get(&1), Some(&11));
+        assert_eq!(map.len(), 1);
+
+        // Occupied: `or_insert` on an existing key returns the existing
+        // value rather than overwriting it with the given default.
+        *map.entry(1).or_insert(999) += 1;
+        assert_eq!(map.get(&1), Some(&12));
+        assert_eq!(map.len(), 1);
+
+        map.entry(2).or_insert_with(|| 20);
+        assert_eq!(map.get(&2), Some(&20));
+
+        map.entry(2).and_modify(|v| *v += 1);
+        assert_eq!(map.get(&2), Some(&21));
+
+        // `and_modify` on a vacant entry is a no-op, leaving it vacant
+        // for the following `or_insert` to fill.
+        map.entry(3).and_modify(|v| *v += 1).or_insert(30);
+        assert_eq!(map.get(&3), Some(&30));
+
+        *map.entry(4).or_default() += 5;
+        assert_eq!(map.get(&4), Some(&5));
+
+        assert_eq!(map.len(), 4);
+
+        match map.entry(1) {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.get(), &12);
+                assert_eq!(entry.insert(13), 12);
+                assert_eq!(entry.into_mut(), &mut 13);
+            }
+            Entry::Vacant(_) => panic!("key 1 should be occupied"),
+        }
+        assert_eq!(map.get(&1), Some(&13));
+    }
+
+    #[test]
+    fn entry_reuses_tombstoned_slot_after_remove() {
+        let mut map = NewMap::<u8, u64>::from_iter([(1, 10), (2, 20)]);
+        assert_eq!(map.remove(&1), Some(10));
+        assert_eq!(map.len(), 1);
+
+        // Re-inserting the removed key through `entry` must reuse its
+        // old slot instead of extending the shared key chain, and must
+        // bump `count` exactly once.
+        let value = map.entry(1).or_insert(100);
+        assert_eq!(*value, 100);
+        assert_eq!(map.len(), 2);
+
+        let mut pairs: Vec<_> = map.iter().collect();
+        pairs.sort();
+        assert_eq!(pairs, vec![(&1, &100), (&2, &20)]);
+    }
+
+    #[test]
+    fn mutates_through_get_mut_and_values_mut_and_iter_mut() {
+        let mut map = NewMap::<u8, u64>::from_iter([(1, 10), (2, 20), (3, 30)]);
+        map.remove(&2);
+
+        *map.get_mut(&1).unwrap() += 1;
+        assert_eq!(map.get(&1), Some(&11));
+        assert_eq!(map.get_mut(&2), None);
+
+        // Every live slot is visited exactly once, and the tombstoned
+        // slot left by `remove` is skipped.
+        for value in map.values_mut() {
+            *value *= 10;
+        }
+        let mut pairs: Vec<_> = map.iter().collect();
+        pairs.sort();
+        assert_eq!(pairs, vec![(&1, &110), (&3, &300)]);
+
+        for (_, value) in map.iter_mut() {
+            *value += 1;
+        }
+        let mut pairs: Vec<_> = map.iter().collect();
+        pairs.sort();
+        assert_eq!(pairs, vec![(&1, &111), (&3, &301)]);
+    }
+
+    #[test]
+    fn string_keyed_map_is_queryable_by_str() {
+        let mut map = NewMap::<String, u32>::default();
+        map.insert("alpha".to_string(), 1);
+        map.insert("beta".to_string(), 2);
+
+        // `Borrow<str>` for `String` means these lookups can take a
+        // `&str` literal directly, with no owned `String` allocated.
+        assert_eq!(map.get("alpha"), Some(&1));
+        assert_eq!(map.get("missing"), None);
+
+        *map.get_mut("beta").unwrap() += 1;
+        assert_eq!(map.get("beta"), Some(&3));
+
+        assert_eq!(map.remove("alpha"), Some(1));
+        assert_eq!(map.get("alpha"), None);
+        assert_eq!(map.get("beta"), Some(&3));
+    }
+
     #[derive(Arbitrary, Debug)]
     enum Action<K, V> {
         Get(K),
@@ -98,4 +224,57 @@ mod tests {
             run_tests(start, &acts);
         }
     }
+
+    fn compare_persistent_map<K, V>(this: &BTreeMap<K, V>, that: &PersistentMap<K, V>)
+    where
+        K: Clone + Debug + Eq + Hash + Ord + Send + Sync + 'static,
+        V: Debug + Eq + Ord,
+    {
+        let mut this: Vec<_> = this.iter().collect();
+        let mut that: Vec<_> = that.iter().collect();
+        this.sort();
+        that.sort();
+        assert_eq!(this, that);
+    }
+
+    fn run_persistent_tests<K, V>(start: Vec<(K, V)>, acts: &[Action<K, V>])
+    where
+        K: Clone + Debug + Eq + Hash + Ord + Send + Sync + 'static,
+        V: Clone + Debug + Eq + Ord,
+    {
+        let mut baseline = BTreeMap::from_iter(start.clone());
+        let mut sut = PersistentMap::from_iter(start);
+
+        compare_persistent_map(&baseline, &sut);
+
+        for act in acts {
+            match act {
+                Action::Get(key) => {
+                    assert_eq!(baseline.get(key), sut.get(key));
+                }
+                Action::Insert(key, value) => {
+                    baseline.insert(key.clone(), value.clone());
+                    sut = sut.insert(key.clone(), value.clone());
+                }
+                Action::Remove(key) => {
+                    baseline.remove(key);
+                    sut = sut.remove(key);
+                }
+            }
+            compare_persistent_map(&baseline, &sut);
+        }
+    }
+
+    proptest! {
+        /// Mirrors `behaves_like_btreemap`, but exercises `PersistentMap`'s
+        /// immutable insert/remove (which return a new map) instead of
+        /// `NewMap`'s in-place mutation.
+        #[test]
+        fn persistent_behaves_like_btreemap(
+            start in any::<Vec<(u8, i64)>>(),
+            acts in any::<Vec<Action<u8, i64>>>()
+        ) {
+            run_persistent_tests(start, &acts);
+        }
+    }
 }
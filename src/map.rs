@@ -1,4 +1,5 @@
-use std::collections::hash_map;
+use std::borrow::Borrow;
+use std::collections::HashMap;
 use std::hash::Hash;
 use std::marker::PhantomData;
 
@@ -33,6 +34,33 @@ impl<K: Eq + Hash, V, S> NewMap<K, V, S> {
     pub fn is_empty(&self) -> bool {
         self.count == 0
     }
+
+    #[cfg(feature = "rayon")]
+    pub(crate) fn shared_keys(&self) -> &SharedKeys<K> {
+        &self.keys
+    }
+
+    #[cfg(feature = "rayon")]
+    pub(crate) fn raw_values(&self) -> &[Option<V>] {
+        &self.values
+    }
+
+    // Returns the key and value halves from a single borrow of `self`,
+    // rather than two separate accessor calls: `&mut self.values` next
+    // to a `&self.keys` borrowed through its own method call doesn't
+    // type-check, since the borrow checker can't see that the two
+    // accessors touch disjoint fields.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn raw_parts_mut(&mut self) -> (&SharedKeys<K>, &mut [Option<V>]) {
+        (&self.keys, &mut self.values)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<K: Clone + Eq + Hash, V, S> NewMap<K, V, S> {
+    pub(crate) fn into_raw_parts(self) -> (Vec<K>, Vec<Option<V>>) {
+        (self.keys.ordered_keys().to_vec(), self.values)
+    }
 }
 
 impl<K: Eq + Hash, V, S: KeyStore<K>> NewMap<K, V, S> {
@@ -42,15 +70,52 @@ impl<K: Eq + Hash, V, S: KeyStore<K>> NewMap<K, V, S> {
 }
 
 impl<K: Eq + Hash, V, S: KeyStore<K>> NewMap<K, V, S> {
-    pub fn get(&self, key: &K) -> Option<&V> {
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         self.keys
             .get_index(key)
             .and_then(|i| self.values[i].as_ref())
     }
 
+    /// Iterates over the map's pairs in insertion order.
     pub fn iter(&self) -> Iter<'_, K, V> {
         self.into_iter()
     }
+
+    /// Iterates over the map's keys in insertion order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys(self.iter())
+    }
+
+    /// Iterates over the map's values in insertion order.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values(self.iter())
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.keys.get_index(key)?;
+        self.values[index].as_mut()
+    }
+
+    /// Mutably iterates over the map's pairs in insertion order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            keys: self.keys.ordered_keys(),
+            values: &mut self.values,
+        }
+    }
+
+    /// Mutably iterates over the map's values in insertion order.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut(self.iter_mut())
+    }
 }
 
 impl<K: Clone + Eq + Hash + Ord, V, S> NewMap<K, V, S> {
@@ -69,7 +134,11 @@ impl<K: Clone + Eq + Hash + Ord, V, S> NewMap<K, V, S> {
         }
     }
 
-    pub fn remove(&mut self, key: &K) -> Option<V> {
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         self.keys
             .indices()
             .get(key)
@@ -79,17 +148,152 @@ impl<K: Clone + Eq + Hash + Ord, V, S> NewMap<K, V, S> {
                 value
             })
     }
+
+    /// Gets the given key's corresponding entry in the map for in-place
+    /// insertion, removal-aware modification, or lookup.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        match self.keys.indices().get(&key) {
+            Some(&index) if self.values[index].is_some() => {
+                Entry::Occupied(OccupiedEntry { map: self, index })
+            }
+            Some(&index) => Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                index: Some(index),
+            }),
+            None => Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                index: None,
+            }),
+        }
+    }
+}
+
+/// A view into a single entry in a [`NewMap`], which may either be
+/// vacant or occupied, obtained from [`NewMap::entry`].
+pub enum Entry<'a, K: Eq + Hash, V, S> {
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+impl<'a, K: Clone + Eq + Hash + Ord, V, S> Entry<'a, K, V, S> {
+    /// Ensures a value is in the entry by inserting `default` if vacant,
+    /// then returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default`
+    /// if vacant, then returns a mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K: Clone + Eq + Hash + Ord, V: Default, S> Entry<'a, K, V, S> {
+    /// Ensures a value is in the entry by inserting the default value if
+    /// vacant, then returns a mutable reference to the value.
+    pub fn or_default(self) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(V::default()),
+        }
+    }
+}
+
+/// A view into an occupied entry in a [`NewMap`].
+pub struct OccupiedEntry<'a, K: Eq + Hash, V, S> {
+    map: &'a mut NewMap<K, V, S>,
+    index: usize,
+}
+
+impl<'a, K: Eq + Hash, V, S> OccupiedEntry<'a, K, V, S> {
+    pub fn get(&self) -> &V {
+        self.map.values[self.index].as_ref().unwrap()
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.map.values[self.index].as_mut().unwrap()
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self.map.values[self.index].as_mut().unwrap()
+    }
+
+    pub fn insert(&mut self, value: V) -> V {
+        self.map.values[self.index].replace(value).unwrap()
+    }
+}
+
+/// A view into a vacant entry in a [`NewMap`].
+pub struct VacantEntry<'a, K: Eq + Hash, V, S> {
+    map: &'a mut NewMap<K, V, S>,
+    key: K,
+    index: Option<usize>,
+}
+
+impl<'a, K: Clone + Eq + Hash + Ord, V, S> VacantEntry<'a, K, V, S> {
+    pub fn insert(self, value: V) -> &'a mut V {
+        let index = match self.index {
+            // The key was already present but its slot had been vacated by
+            // a previous `remove`, so reuse that slot instead of extending
+            // the shared key chain.
+            Some(index) => index,
+            None => {
+                self.map.keys = self.map.keys.insert(self.key);
+                let index = self.map.values.len();
+                self.map.values.push(None);
+                index
+            }
+        };
+        self.map.count += 1;
+        self.map.values[index] = Some(value);
+        self.map.values[index].as_mut().unwrap()
+    }
 }
 
 impl<K: Eq + Hash + Ord, V, S: KeyStore<K>> FromIterator<(K, V)> for NewMap<K, V, S> {
     fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
-        let (keys, values): (Vec<_>, Vec<_>) = iter
+        let (keys, mut values): (Vec<_>, Vec<_>) = iter
             .into_iter()
             .map(|(key, value)| (key, Some(value)))
             .multiunzip();
+
+        // A repeated key's earlier occurrences are superseded by its
+        // last one, matching `insert`'s overwrite semantics (and the
+        // last-write-wins dedup `SharedKeys` applies to `keys` below);
+        // null out those stale slots so they don't surface as phantom
+        // extra pairs during iteration.
+        let mut last_index = HashMap::with_capacity(keys.len());
+        for (index, key) in keys.iter().enumerate() {
+            last_index.insert(key, index);
+        }
+        for (index, value) in values.iter_mut().enumerate() {
+            if last_index[&keys[index]] != index {
+                *value = None;
+            }
+        }
+        let count = values.iter().filter(|value| value.is_some()).count();
+
         let keys = S::get(keys);
         Self {
-            count: values.len(),
+            count,
             keys,
             values,
             _store: Default::default(),
@@ -102,53 +306,121 @@ impl<'a, K: Eq + Hash, V, S> IntoIterator for &'a NewMap<K, V, S> {
     type IntoIter = Iter<'a, K, V>;
     fn into_iter(self) -> Self::IntoIter {
         Iter {
-            keys: self.keys.indices().iter(),
+            keys: self.keys.ordered_keys(),
             values: &self.values,
+            index: 0,
         }
     }
 }
 
-pub struct Iter<'a, K: Eq + Hash, V> {
-    keys: hash_map::Iter<'a, K, usize>,
-    values: &'a Vec<Option<V>>,
+/// Iterator over `(&K, &V)` pairs of a [`NewMap`] in insertion order.
+pub struct Iter<'a, K, V> {
+    keys: &'a [K],
+    values: &'a [Option<V>],
+    index: usize,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    pub(crate) fn new(keys: &'a [K], values: &'a [Option<V>]) -> Self {
+        Self {
+            keys,
+            values,
+            index: 0,
+        }
+    }
 }
 
-impl<'a, K: Eq + Hash, V> Iterator for Iter<'a, K, V> {
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
     type Item = (&'a K, &'a V);
     fn next(&mut self) -> Option<Self::Item> {
-        for (key, &index) in self.keys.by_ref() {
+        while self.index < self.values.len() {
+            let index = self.index;
+            self.index += 1;
             if let Some(value) = self.values[index].as_ref() {
-                return Some((key, value));
+                return Some((&self.keys[index], value));
             }
         }
         None
     }
 }
 
+/// Iterator over the keys of a [`NewMap`] in insertion order.
+pub struct Keys<'a, K, V>(Iter<'a, K, V>);
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, _)| key)
+    }
+}
+
+/// Iterator over the values of a [`NewMap`] in insertion order.
+pub struct Values<'a, K, V>(Iter<'a, K, V>);
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, value)| value)
+    }
+}
+
+/// Iterator over `(&K, &mut V)` pairs of a [`NewMap`] in insertion order.
+pub struct IterMut<'a, K, V> {
+    keys: &'a [K],
+    values: &'a mut [Option<V>],
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, rest_keys) = self.keys.split_first()?;
+            let (value, rest_values) = std::mem::take(&mut self.values).split_first_mut()?;
+            self.keys = rest_keys;
+            self.values = rest_values;
+            if let Some(value) = value.as_mut() {
+                return Some((key, value));
+            }
+        }
+    }
+}
+
+/// Iterator over the mutable values of a [`NewMap`] in insertion order.
+pub struct ValuesMut<'a, K, V>(IterMut<'a, K, V>);
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, value)| value)
+    }
+}
+
 impl<K: Clone + Eq + Hash, V, S> IntoIterator for NewMap<K, V, S> {
     type Item = (K, V);
     type IntoIter = IntoIter<K, V>;
     fn into_iter(self) -> Self::IntoIter {
         IntoIter {
-            keys: self.keys.indices().clone().into_iter(),
-            values: self.values,
+            keys: self.keys.ordered_keys().to_vec().into_iter(),
+            values: self.values.into_iter(),
         }
     }
 }
 
+/// Owning iterator over `(K, V)` pairs of a [`NewMap`] in insertion order.
 pub struct IntoIter<K, V> {
-    keys: hash_map::IntoIter<K, usize>,
-    values: Vec<Option<V>>,
+    keys: std::vec::IntoIter<K>,
+    values: std::vec::IntoIter<Option<V>>,
 }
 
 impl<K, V> Iterator for IntoIter<K, V> {
     type Item = (K, V);
     fn next(&mut self) -> Option<Self::Item> {
-        for (key, index) in self.keys.by_ref() {
-            if let Some(value) = self.values[index].take() {
+        loop {
+            let key = self.keys.next()?;
+            let value = self.values.next()?;
+            if let Some(value) = value {
                 return Some((key, value));
             }
         }
-        None
     }
 }
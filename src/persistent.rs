@@ -0,0 +1,204 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+
+use super::keystore::{KeyStore, SharedKeys, StdKeyStore};
+use super::map::Iter;
+
+/// A persistent map whose `insert` and `remove` return a new map instead
+/// of mutating in place, leaving the original unchanged.
+///
+/// Like [`NewMap`](super::NewMap), the key side is backed by the
+/// `Arc`-shared [`SharedKeys`] insert chain. The value side is likewise
+/// held in an `Arc<Vec<Option<V>>>`, so cloning a `PersistentMap` is an
+/// `Arc` clone rather than a deep copy. `insert` and `remove` go through
+/// `Arc::make_mut` while `self` still holds its own reference, so the
+/// returned map always gets a full copy of the value vector; only the
+/// keys stay shared between a map and one derived from it.
+pub struct PersistentMap<K: Eq + Hash, V, S = StdKeyStore<K>> {
+    keys: SharedKeys<K>,
+    values: Arc<Vec<Option<V>>>,
+    count: usize,
+    _store: PhantomData<S>,
+}
+
+impl<K: Eq + Hash, V, S> Clone for PersistentMap<K, V, S> {
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            values: Arc::clone(&self.values),
+            count: self.count,
+            _store: PhantomData,
+        }
+    }
+}
+
+impl<K: Eq + Hash, V, S: KeyStore<K>> Default for PersistentMap<K, V, S> {
+    fn default() -> Self {
+        let keys = S::get(Vec::new());
+        Self {
+            keys,
+            values: Arc::new(Vec::new()),
+            count: 0,
+            _store: PhantomData,
+        }
+    }
+}
+
+impl<K: Eq + Hash, V, S> PersistentMap<K, V, S> {
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+impl<K: Eq + Hash, V, S: KeyStore<K>> PersistentMap<K, V, S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K: Eq + Hash, V, S: KeyStore<K>> PersistentMap<K, V, S> {
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.keys
+            .get_index(key)
+            .and_then(|i| self.values[i].as_ref())
+    }
+
+    /// Iterates over the map's pairs in insertion order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.into_iter()
+    }
+}
+
+impl<K: Clone + Eq + Hash + Ord, V: Clone, S> PersistentMap<K, V, S> {
+    /// Returns a new map with `key` set to `value`, leaving `self`
+    /// unchanged. The new map holds its own copy of the value vector.
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let mut keys = self.keys.clone();
+        let mut values = Arc::clone(&self.values);
+        let mut count = self.count;
+        match keys.indices().get(&key) {
+            Some(&index) => {
+                let old = Arc::make_mut(&mut values)[index].replace(value);
+                if old.is_none() {
+                    count += 1;
+                }
+            }
+            None => {
+                keys = keys.insert(key);
+                Arc::make_mut(&mut values).push(Some(value));
+                count += 1;
+            }
+        }
+        Self {
+            keys,
+            values,
+            count,
+            _store: PhantomData,
+        }
+    }
+
+    /// Returns a new map with `key` removed, leaving `self` unchanged. The
+    /// new map holds its own copy of the value vector.
+    pub fn remove<Q>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut values = Arc::clone(&self.values);
+        let mut count = self.count;
+        if let Some(index) = self.keys.get_index(key) {
+            if Arc::make_mut(&mut values)[index].take().is_some() {
+                count -= 1;
+            }
+        }
+        Self {
+            keys: self.keys.clone(),
+            values,
+            count,
+            _store: PhantomData,
+        }
+    }
+}
+
+impl<K: Eq + Hash + Ord, V, S: KeyStore<K>> FromIterator<(K, V)> for PersistentMap<K, V, S> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let (keys, mut values): (Vec<_>, Vec<_>) = iter
+            .into_iter()
+            .map(|(key, value)| (key, Some(value)))
+            .multiunzip();
+
+        // See the matching comment on `NewMap`'s `FromIterator`: a
+        // repeated key's earlier occurrences must be nulled out so they
+        // don't surface as phantom extra pairs during iteration.
+        let mut last_index = HashMap::with_capacity(keys.len());
+        for (index, key) in keys.iter().enumerate() {
+            last_index.insert(key, index);
+        }
+        for (index, value) in values.iter_mut().enumerate() {
+            if last_index[&keys[index]] != index {
+                *value = None;
+            }
+        }
+        let count = values.iter().filter(|value| value.is_some()).count();
+
+        let keys = S::get(keys);
+        Self {
+            count,
+            keys,
+            values: Arc::new(values),
+            _store: PhantomData,
+        }
+    }
+}
+
+impl<'a, K: Eq + Hash, V, S> IntoIterator for &'a PersistentMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(self.keys.ordered_keys(), &self.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clone_shares_storage_until_mutated() {
+        let map = PersistentMap::<u8, u64>::from_iter([(1, 10), (2, 20)]);
+        let clone = map.clone();
+
+        // `Clone` is an `Arc` clone, not a deep copy: both maps point at
+        // the same value storage until one of them is mutated.
+        assert!(Arc::ptr_eq(&map.values, &clone.values));
+        assert_eq!(Arc::strong_count(&map.values), 2);
+    }
+
+    #[test]
+    fn insert_and_remove_return_a_new_map_leaving_the_original_untouched() {
+        let base = PersistentMap::<u8, u64>::from_iter([(1, 10), (2, 20)]);
+
+        let inserted = base.insert(3, 30);
+        assert_eq!(base.get(&3), None);
+        assert_eq!(inserted.get(&3), Some(&30));
+        assert_eq!(inserted.get(&1), Some(&10));
+
+        let removed = inserted.remove(&1);
+        assert_eq!(inserted.get(&1), Some(&10));
+        assert_eq!(removed.get(&1), None);
+        assert_eq!(removed.get(&2), Some(&20));
+    }
+}
@@ -0,0 +1,183 @@
+//! Parallel iteration over [`NewMap`], enabled by the `rayon` feature.
+//!
+//! Mirrors indexmap's `rayon` module: pairs, keys, and values can all be
+//! traversed with a [`rayon::iter::ParallelIterator`], driven by a
+//! parallel bridge over the dense `values` index range, skipping
+//! tombstoned slots left behind by `remove`.
+
+use std::hash::Hash;
+
+use rayon::iter::plumbing::UnindexedConsumer;
+use rayon::prelude::*;
+
+use super::map::NewMap;
+
+impl<'a, K: Eq + Hash + Sync, V: Sync, S> IntoParallelIterator for &'a NewMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type Iter = ParIter<'a, K, V>;
+    fn into_par_iter(self) -> Self::Iter {
+        ParIter {
+            keys: self.shared_keys().ordered_keys(),
+            values: self.raw_values(),
+        }
+    }
+}
+
+/// Parallel iterator over `(&K, &V)` pairs of a [`NewMap`].
+pub struct ParIter<'a, K, V> {
+    keys: &'a [K],
+    values: &'a [Option<V>],
+}
+
+impl<'a, K: Sync, V: Sync> ParallelIterator for ParIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let keys = self.keys;
+        let values = self.values;
+        (0..values.len())
+            .into_par_iter()
+            .filter_map(move |i| values[i].as_ref().map(|value| (&keys[i], value)))
+            .drive_unindexed(consumer)
+    }
+}
+
+impl<'a, K: Eq + Hash + Sync, V: Send, S> IntoParallelIterator for &'a mut NewMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type Iter = ParIterMut<'a, K, V>;
+    fn into_par_iter(self) -> Self::Iter {
+        let (keys, values) = self.raw_parts_mut();
+        ParIterMut {
+            keys: keys.ordered_keys(),
+            values,
+        }
+    }
+}
+
+/// Parallel iterator over `(&K, &mut V)` pairs of a [`NewMap`].
+pub struct ParIterMut<'a, K, V> {
+    keys: &'a [K],
+    values: &'a mut [Option<V>],
+}
+
+impl<'a, K: Sync, V: Send> ParallelIterator for ParIterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let keys = self.keys;
+        self.values
+            .par_iter_mut()
+            .enumerate()
+            .filter_map(move |(i, slot)| slot.as_mut().map(|value| (&keys[i], value)))
+            .drive_unindexed(consumer)
+    }
+}
+
+impl<K: Clone + Eq + Hash + Send, V: Send, S> IntoParallelIterator for NewMap<K, V, S> {
+    type Item = (K, V);
+    type Iter = IntoParIter<K, V>;
+    fn into_par_iter(self) -> Self::Iter {
+        let (keys, values) = self.into_raw_parts();
+        IntoParIter { keys, values }
+    }
+}
+
+/// Parallel iterator over owned `(K, V)` pairs of a [`NewMap`].
+pub struct IntoParIter<K, V> {
+    keys: Vec<K>,
+    values: Vec<Option<V>>,
+}
+
+impl<K: Send, V: Send> ParallelIterator for IntoParIter<K, V> {
+    type Item = (K, V);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.keys
+            .into_par_iter()
+            .zip(self.values.into_par_iter())
+            .filter_map(|(key, value)| value.map(|value| (key, value)))
+            .drive_unindexed(consumer)
+    }
+}
+
+impl<K: Eq + Hash, V, S> NewMap<K, V, S> {
+    /// Iterates over the map's pairs in parallel; order is not
+    /// guaranteed to match `iter`'s insertion order.
+    pub fn par_iter(&self) -> ParIter<'_, K, V>
+    where
+        K: Sync,
+        V: Sync,
+    {
+        self.into_par_iter()
+    }
+
+    /// Iterates over the map's keys in parallel.
+    pub fn par_keys(&self) -> impl ParallelIterator<Item = &K>
+    where
+        K: Sync,
+        V: Sync,
+    {
+        self.par_iter().map(|(key, _)| key)
+    }
+
+    /// Iterates over the map's values in parallel.
+    pub fn par_values(&self) -> impl ParallelIterator<Item = &V>
+    where
+        K: Sync,
+        V: Sync,
+    {
+        self.par_iter().map(|(_, value)| value)
+    }
+
+    /// Mutably iterates over the map's values in parallel.
+    pub fn par_values_mut(&mut self) -> impl ParallelIterator<Item = &mut V>
+    where
+        K: Sync,
+        V: Send,
+    {
+        self.into_par_iter().map(|(_, value)| value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn par_iter_matches_sequential_iter_and_skips_tombstones() {
+        let mut map = NewMap::<u8, u64>::from_iter([(1, 10), (2, 20), (3, 30)]);
+        map.remove(&2);
+
+        assert_eq!(map.par_iter().count(), map.len());
+
+        let mut expected: Vec<_> = map.iter().map(|(&k, &v)| (k, v)).collect();
+        let mut actual: Vec<_> = map.par_iter().map(|(&k, &v)| (k, v)).collect();
+        expected.sort();
+        actual.sort();
+        assert_eq!(expected, actual);
+
+        let sum: u64 = map.par_values().sum();
+        assert_eq!(sum, 40);
+    }
+
+    #[test]
+    fn par_values_mut_mutates_every_live_slot_exactly_once() {
+        let mut map = NewMap::<u8, u64>::from_iter([(1, 10), (2, 20), (3, 30)]);
+        map.remove(&2);
+
+        map.par_values_mut().for_each(|value| *value *= 10);
+
+        let mut pairs: Vec<_> = map.iter().collect();
+        pairs.sort();
+        assert_eq!(pairs, vec![(&1, &100), (&3, &300)]);
+    }
+}
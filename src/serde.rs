@@ -0,0 +1,108 @@
+//! `serde` support for [`NewMap`], enabled by the `serde` feature.
+//!
+//! Deserializing routes the collected pairs through
+//! `NewMap`'s `FromIterator` impl, so a batch of maps with identical
+//! schemas deserializes back into maps that share one `SharedKeys` via
+//! the global key interning pool. A duplicate key in the input collapses
+//! to its last occurrence, matching `HashMap`/`BTreeMap`'s own
+//! `FromIterator` behavior, rather than erroring or panicking.
+
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::keystore::KeyStore;
+use super::map::NewMap;
+
+impl<K: Eq + Hash + Serialize, V: Serialize, S> Serialize for NewMap<K, V, S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        // `self`, not `self.iter()`: `iter` is only defined for
+        // `S: KeyStore<K>`, but serializing doesn't need the key store
+        // at all, and `&NewMap` already implements `IntoIterator`.
+        serializer.collect_map(self)
+    }
+}
+
+impl<'de, K, V, S> Deserialize<'de> for NewMap<K, V, S>
+where
+    K: Eq + Hash + Ord + Deserialize<'de>,
+    V: Deserialize<'de>,
+    S: KeyStore<K>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MapVisitor<K, V, S>(PhantomData<(K, V, S)>);
+
+        impl<'de, K, V, S> Visitor<'de> for MapVisitor<K, V, S>
+        where
+            K: Eq + Hash + Ord + Deserialize<'de>,
+            V: Deserialize<'de>,
+            S: KeyStore<K>,
+        {
+            type Value = NewMap<K, V, S>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut pairs = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(pair) = map.next_entry()? {
+                    pairs.push(pair);
+                }
+                Ok(NewMap::from_iter(pairs))
+            }
+        }
+
+        deserializer.deserialize_map(MapVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystore::StdKeyStore;
+
+    fn sorted_pairs<K: Ord + Clone + std::hash::Hash, V: Clone, S: KeyStore<K>>(
+        map: &NewMap<K, V, S>,
+    ) -> Vec<(K, V)> {
+        let mut pairs: Vec<_> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+        pairs
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let map = NewMap::<u8, u32>::from_iter([(1, 10), (2, 20), (3, 30)]);
+        let json = serde_json::to_string(&map).unwrap();
+        let back: NewMap<u8, u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(sorted_pairs(&map), sorted_pairs(&back));
+    }
+
+    #[test]
+    fn duplicate_key_collapses_to_last_occurrence() {
+        let map: NewMap<u8, u32> = serde_json::from_str(r#"{"1":10,"2":20,"1":11}"#).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(sorted_pairs(&map), vec![(1, 11), (2, 20)]);
+    }
+
+    #[test]
+    fn deserialize_shares_shared_keys_across_same_schema_maps() {
+        // `u16` is used as the key type solely so this test's view of the
+        // global per-type key-store singleton doesn't overlap with any
+        // other test's.
+        let json = r#"{"1":10,"2":20,"3":30}"#;
+        let before = StdKeyStore::<u16>::stats().total_maps;
+
+        let a: NewMap<u16, u32> = serde_json::from_str(json).unwrap();
+        let after_first = StdKeyStore::<u16>::stats().total_maps;
+        assert_eq!(after_first, before + 1);
+
+        let b: NewMap<u16, u32> = serde_json::from_str(json).unwrap();
+        assert_eq!(StdKeyStore::<u16>::stats().total_maps, after_first);
+
+        assert_eq!(sorted_pairs(&a), sorted_pairs(&b));
+    }
+}
@@ -0,0 +1,132 @@
+//! Sequence-based `serde` representation for [`NewMap`], for formats
+//! where map-key ordering (or non-string keys) isn't reliably supported.
+//!
+//! Use via a field attribute:
+//!
+//! ```ignore
+//! #[serde(with = "vecmap::serde_seq")]
+//! map: NewMap<K, V>,
+//! ```
+//!
+//! As with the map-shaped representation, `deserialize` collapses a
+//! duplicate key to its last occurrence (via `NewMap`'s `FromIterator`)
+//! instead of erroring or panicking.
+
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserializer, Serialize, Serializer};
+
+use super::keystore::KeyStore;
+use super::map::NewMap;
+
+pub fn serialize<K, V, S, Ser>(
+    map: &NewMap<K, V, S>,
+    serializer: Ser,
+) -> Result<Ser::Ok, Ser::Error>
+where
+    K: Eq + Hash + Serialize,
+    V: Serialize,
+    Ser: Serializer,
+{
+    // `map`, not `map.iter()`: `iter` is only defined for
+    // `S: KeyStore<K>`, but serializing doesn't need the key store at
+    // all, and `&NewMap` already implements `IntoIterator`.
+    serializer.collect_seq(map)
+}
+
+pub fn deserialize<'de, D, K, V, S>(deserializer: D) -> Result<NewMap<K, V, S>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Eq + Hash + Ord + serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+    S: KeyStore<K>,
+{
+    struct SeqVisitor<K, V, S>(PhantomData<(K, V, S)>);
+
+    impl<'de, K, V, S> Visitor<'de> for SeqVisitor<K, V, S>
+    where
+        K: Eq + Hash + Ord + serde::Deserialize<'de>,
+        V: serde::Deserialize<'de>,
+        S: KeyStore<K>,
+    {
+        type Value = NewMap<K, V, S>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a sequence of key-value pairs")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut pairs = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(pair) = seq.next_element()? {
+                pairs.push(pair);
+            }
+            Ok(NewMap::from_iter(pairs))
+        }
+    }
+
+    deserializer.deserialize_seq(SeqVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystore::StdKeyStore;
+
+    fn sorted_pairs<K: Ord + Clone + std::hash::Hash, V: Clone, S: KeyStore<K>>(
+        map: &NewMap<K, V, S>,
+    ) -> Vec<(K, V)> {
+        let mut pairs: Vec<_> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+        pairs
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Wrapper {
+        #[serde(with = "crate::serde_seq")]
+        map: NewMap<u8, u32>,
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let wrapper = Wrapper {
+            map: NewMap::from_iter([(1, 10), (2, 20), (3, 30)]),
+        };
+        let json = serde_json::to_string(&wrapper).unwrap();
+        let back: Wrapper = serde_json::from_str(&json).unwrap();
+        assert_eq!(sorted_pairs(&wrapper.map), sorted_pairs(&back.map));
+    }
+
+    #[test]
+    fn duplicate_key_collapses_to_last_occurrence() {
+        let wrapper: Wrapper = serde_json::from_str(r#"{"map":[[1,10],[2,20],[1,11]]}"#).unwrap();
+        assert_eq!(wrapper.map.len(), 2);
+        assert_eq!(sorted_pairs(&wrapper.map), vec![(1, 11), (2, 20)]);
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct InterningWrapper {
+        // `i16` is used as the key type solely so this test's view of the
+        // global per-type key-store singleton doesn't overlap with any
+        // other test's.
+        #[serde(with = "crate::serde_seq")]
+        map: NewMap<i16, u32>,
+    }
+
+    #[test]
+    fn deserialize_shares_shared_keys_across_same_schema_maps() {
+        let json = r#"{"map":[[1,10],[2,20],[3,30]]}"#;
+        let before = StdKeyStore::<i16>::stats().total_maps;
+
+        let a: InterningWrapper = serde_json::from_str(json).unwrap();
+        let after_first = StdKeyStore::<i16>::stats().total_maps;
+        assert_eq!(after_first, before + 1);
+
+        let b: InterningWrapper = serde_json::from_str(json).unwrap();
+        assert_eq!(StdKeyStore::<i16>::stats().total_maps, after_first);
+
+        assert_eq!(sorted_pairs(&a.map), sorted_pairs(&b.map));
+    }
+}